@@ -1,17 +1,47 @@
 use argon2::password_hash::rand_core::OsRng;
 use argon2::password_hash::{PasswordHashString, SaltString};
-use argon2::{Argon2, PasswordHasher, PasswordVerifier};
+use argon2::{Argon2, Params, PasswordHasher, PasswordVerifier, Version};
 use base64::prelude::{Engine, BASE64_STANDARD};
 use futures::FutureExt;
-use gloo::storage::{LocalStorage, Storage};
+use gloo::events::EventListener;
+use gloo::storage::{LocalStorage, SessionStorage, Storage};
+use gloo::timers::callback::Timeout;
 use sha2::{digest, Sha256};
+use std::fmt;
+use std::ops::Deref;
+use std::sync::LazyLock;
 use std::time::Duration;
 use strum::{EnumMessage, IntoEnumIterator};
 use wasm_bindgen_futures::JsFuture;
-use web_sys::{Event, HtmlInputElement, HtmlSelectElement};
+use web_sys::wasm_bindgen::{JsCast, JsValue};
+use web_sys::{
+    Element, Event, HtmlAnchorElement, HtmlElement, HtmlInputElement, HtmlSelectElement,
+    KeyboardEvent, MediaQueryList,
+};
 use yew::TargetCast;
+use zeroize::{Zeroize, Zeroizing};
 
 const STORAGE_KEY: &str = concat!(env!("CARGO_BIN_NAME"), "/hash");
+const THEME_STORAGE_KEY: &str = concat!(env!("CARGO_BIN_NAME"), "/theme");
+
+// https://docs.rs/blake3/latest/blake3/fn.derive_key.html
+const BLAKE3_256_CONTEXT: &str = "s2k 2025-01-01 key derivation";
+const BLAKE3_512_CONTEXT: &str = "s2k 2025-01-01 key derivation 512";
+const WIREGUARD_CONTEXT: &str = "s2k 2025-01-01 wireguard private key";
+const AGE_CONTEXT: &str = "s2k 2025-01-01 age identity";
+const SSH_ED25519_CONTEXT: &str = "s2k 2025-01-01 ssh ed25519 seed";
+const HMAC_SHA256_CONTEXT: &str = "s2k 2025-01-01 hmac-sha256 otp secret";
+const HMAC_SHA512_CONTEXT: &str = "s2k 2025-01-01 hmac-sha512 otp secret";
+const DICEWARE5_CONTEXT: &str = "s2k 2025-01-01 diceware 5 words";
+const DICEWARE6_CONTEXT: &str = "s2k 2025-01-01 diceware 6 words";
+
+// https://www.eff.org/dice (CC BY 3.0 US)
+static EFF_LARGE_WORDLIST: LazyLock<Vec<&str>> = LazyLock::new(|| {
+    include_str!("../assets/eff_large_wordlist.txt")
+        .lines()
+        .map(|line| line.split_once('\t').unwrap().1)
+        .collect()
+});
 
 fn main() {
     yew::Renderer::<App>::new().render();
@@ -20,36 +50,392 @@ fn main() {
 enum Message {
     Password(String),
     HashExpected(Option<PasswordHashString>),
+    HashActualRefresh,
     Algorithm(Algorithm),
     Salt(String),
+    StorageArea(StorageArea),
+    Theme(Theme),
+    Config(Config),
 }
 
 #[derive(
-    Clone, Copy, PartialEq, strum::Display, strum::EnumIter, strum::EnumMessage, strum::EnumString,
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Eq,
+    Hash,
+    PartialEq,
+    serde::Deserialize,
+    serde::Serialize,
+    strum::Display,
+    strum::EnumIter,
+    strum::EnumMessage,
+    strum::EnumString,
 )]
 enum Algorithm {
+    #[strum(message = "argon2id (128 bits)")]
+    Argon2id128,
+    #[default]
     #[strum(message = "argon2id (256 bits)")]
     Argon2id256,
+    #[strum(message = "argon2id (384 bits)")]
+    Argon2id384,
     #[strum(message = "s2k (SHA256)")]
     S2kSha256,
+    #[strum(message = "diceware (5 words)")]
+    Diceware5,
+    #[strum(message = "diceware (6 words)")]
+    Diceware6,
+    #[strum(message = "WireGuard private key")]
+    WireGuard,
+    #[strum(message = "age identity")]
+    Age,
+    #[strum(message = "argon2id + BLAKE3 (256 bits)")]
+    Blake3_256,
+    #[strum(message = "argon2id + BLAKE3 (512 bits)")]
+    Blake3_512,
+    #[strum(message = "SSH public key (ed25519)")]
+    SshEd25519,
+    #[strum(message = "HMAC-SHA256 key (base32)")]
+    HmacSha256Key,
+    #[strum(message = "HMAC-SHA512 key (base32)")]
+    HmacSha512Key,
+}
+
+impl Algorithm {
+    // every intermediate buffer is wrapped in Zeroizing so that no copy of the
+    // secret outlives the call
+    fn key(self, argon2: &Argon2, password: &[u8], salt: &[u8]) -> Result<DerivedKey, KdfError> {
+        let value = match self {
+            Self::Argon2id128 | Self::Argon2id256 | Self::Argon2id384 => {
+                let len = match self {
+                    Self::Argon2id128 => 16,
+                    Self::Argon2id384 => 48,
+                    _ => 32,
+                };
+                let mut key = Zeroizing::new(vec![0u8; len]);
+                argon2.hash_password_into(password, salt, &mut key)?;
+                Ok(BASE64_STANDARD.encode(&*key))
+            }
+            Self::S2kSha256 => {
+                let mut key = s2k::<Sha256>(salt, password, 65536);
+                let value = BASE64_STANDARD.encode(key);
+                key.as_mut_slice().zeroize();
+                Ok(value)
+            }
+            // one context per word count, so that the five words are not the
+            // start of the six and neither spells out the argon2id key
+            Self::Diceware5 | Self::Diceware6 => {
+                let mut key_material = Zeroizing::new([0u8; 32]);
+                argon2.hash_password_into(password, salt, &mut *key_material)?;
+                let (context, count) = if self == Self::Diceware5 {
+                    (DICEWARE5_CONTEXT, 5)
+                } else {
+                    (DICEWARE6_CONTEXT, 6)
+                };
+                let mut key = Zeroizing::new([0u8; 32]);
+                derive_subkey(context, &*key_material, &mut *key);
+                Ok(encode_words(&*key, &EFF_LARGE_WORDLIST, count).join(" "))
+            }
+            // the same password and salt always give the same private key,
+            // so anyone who knows both can impersonate the peer. the context
+            // keeps it apart from the keys of the other algorithms
+            Self::WireGuard => {
+                let mut key_material = Zeroizing::new([0u8; 32]);
+                argon2.hash_password_into(password, salt, &mut *key_material)?;
+                let mut key = Zeroizing::new([0u8; 32]);
+                derive_subkey(WIREGUARD_CONTEXT, &*key_material, &mut *key);
+                clamp_x25519(&mut key);
+                Ok(BASE64_STANDARD.encode(key.as_slice()))
+            }
+            // a separate context so that it is not the same scalar as WireGuard
+            Self::Age => {
+                let mut key_material = Zeroizing::new([0u8; 32]);
+                argon2.hash_password_into(password, salt, &mut *key_material)?;
+                let mut key = Zeroizing::new([0u8; 32]);
+                derive_subkey(AGE_CONTEXT, &*key_material, &mut *key);
+                clamp_x25519(&mut key);
+                // https://github.com/C2SP/C2SP/blob/main/age.md#the-x25519-recipient-type
+                Ok(bech32::encode_upper::<bech32::Bech32>(
+                    bech32::Hrp::parse_unchecked("age-secret-key-"),
+                    &*key,
+                )
+                .unwrap())
+            }
+            // argon2id hardens the password, BLAKE3 separates the domain. a
+            // longer XOF read would start with the shorter key, hence one
+            // context per length
+            Self::Blake3_256 | Self::Blake3_512 => {
+                let mut key_material = Zeroizing::new([0u8; 32]);
+                argon2.hash_password_into(password, salt, &mut *key_material)?;
+                let (context, len) = if self == Self::Blake3_256 {
+                    (BLAKE3_256_CONTEXT, 32)
+                } else {
+                    (BLAKE3_512_CONTEXT, 64)
+                };
+                let mut key = Zeroizing::new(vec![0u8; len]);
+                derive_subkey(context, &*key_material, &mut key);
+                Ok(BASE64_STANDARD.encode(&*key))
+            }
+            // only the public key is shown; the signing key is dropped right away
+            Self::SshEd25519 => {
+                let mut key_material = Zeroizing::new([0u8; 32]);
+                argon2.hash_password_into(password, salt, &mut *key_material)?;
+                let mut seed = Zeroizing::new([0u8; 32]);
+                derive_subkey(SSH_ED25519_CONTEXT, &*key_material, &mut *seed);
+                let keypair = ssh_key::private::Ed25519Keypair::from_seed(&seed);
+                Ok(
+                    ssh_key::PublicKey::new(keypair.public.into(), "s2k-derived")
+                        .to_openssh()
+                        .unwrap(),
+                )
+            }
+            // an HOTP/TOTP secret, e.g. `oathtool --totp=sha256 -b <key>`. it is
+            // registered with a third party, so it must not reveal the other keys
+            Self::HmacSha256Key | Self::HmacSha512Key => {
+                let mut key_material = Zeroizing::new([0u8; 32]);
+                argon2.hash_password_into(password, salt, &mut *key_material)?;
+                let (context, len) = if self == Self::HmacSha256Key {
+                    (HMAC_SHA256_CONTEXT, 32)
+                } else {
+                    (HMAC_SHA512_CONTEXT, 64)
+                };
+                let mut key = Zeroizing::new(vec![0u8; len]);
+                derive_subkey(context, &*key_material, &mut key);
+                Ok(data_encoding::BASE32_NOPAD.encode(&key))
+            }
+        };
+        value.map(DerivedKey::new)
+    }
+}
+
+// wiped on drop; Debug never prints the key so it cannot leak into logs
+#[derive(Clone, PartialEq)]
+struct DerivedKey(Zeroizing<String>);
+
+impl DerivedKey {
+    fn new(value: impl Into<String>) -> Self {
+        Self(Zeroizing::new(value.into()))
+    }
+}
+
+impl Deref for DerivedKey {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl fmt::Display for DerivedKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl fmt::Debug for DerivedKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("DerivedKey(***)")
+    }
+}
+
+impl Zeroize for DerivedKey {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+#[derive(Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+struct Config {
+    hash: Option<String>,
+    algorithm: Algorithm,
+    theme: Theme,
+}
+
+impl Config {
+    fn parse(value: &str) -> Result<Self, String> {
+        let config = serde_json::from_str::<Self>(value).map_err(|e| e.to_string())?;
+        if let Some(hash) = &config.hash {
+            PasswordHashString::new(hash).map_err(|e| e.to_string())?;
+        }
+        Ok(config)
+    }
+}
+
+// only non-secret settings are shared through the URL
+#[derive(Debug, Default, PartialEq)]
+struct Fragment {
+    algorithm: Option<Algorithm>,
+    salt: Option<String>,
+}
+
+impl Fragment {
+    fn parse(value: &str) -> Self {
+        let mut fragment = Self::default();
+        for (k, v) in form_urlencoded::parse(value.trim_start_matches('#').as_bytes()) {
+            match &*k {
+                "algorithm" => fragment.algorithm = v.parse().ok(),
+                "salt" => fragment.salt = Some(v.into_owned()),
+                _ => (),
+            }
+        }
+        fragment
+    }
 }
 
-impl Default for Algorithm {
-    fn default() -> Self {
-        Self::Argon2id256
+impl fmt::Display for Fragment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut serializer = form_urlencoded::Serializer::new(String::new());
+        if let Some(algorithm) = &self.algorithm {
+            serializer.append_pair("algorithm", &algorithm.to_string());
+        }
+        if let Some(salt) = &self.salt {
+            serializer.append_pair("salt", salt);
+        }
+        write!(f, "#{}", serializer.finish())
     }
 }
 
+#[derive(Clone, Copy, Default, PartialEq)]
+enum StorageArea {
+    #[default]
+    Local,
+    Session,
+}
+
+impl StorageArea {
+    fn get(self) -> Option<String> {
+        match self {
+            Self::Local => LocalStorage::get(STORAGE_KEY).ok(),
+            Self::Session => SessionStorage::get(STORAGE_KEY).ok(),
+        }
+    }
+
+    fn set(self, value: &str) {
+        let _ = match self {
+            Self::Local => LocalStorage::set(STORAGE_KEY, value),
+            Self::Session => SessionStorage::set(STORAGE_KEY, value),
+        };
+    }
+
+    fn delete(self) {
+        match self {
+            Self::Local => LocalStorage::delete(STORAGE_KEY),
+            Self::Session => SessionStorage::delete(STORAGE_KEY),
+        }
+    }
+}
+
+#[derive(Debug)]
+enum KdfError {
+    Argon2(argon2::Error),
+    LowEntropySalt,
+}
+
+impl From<argon2::Error> for KdfError {
+    fn from(value: argon2::Error) -> Self {
+        Self::Argon2(value)
+    }
+}
+
+impl fmt::Display for KdfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::LowEntropySalt => write!(
+                f,
+                "salt should contain at least {MIN_SALT_UNIQUE_BYTES} distinct bytes",
+            ),
+            Self::Argon2(e) => match *e {
+                argon2::Error::AdTooLong => write!(f, "associated data is too long"),
+                argon2::Error::AlgorithmInvalid => write!(f, "unknown argon2 algorithm"),
+                argon2::Error::B64Encoding(e) => write!(f, "invalid base64 encoding: {e}"),
+                argon2::Error::KeyIdTooLong => write!(f, "key ID is too long"),
+                argon2::Error::MemoryTooLittle => write!(f, "memory cost is too small"),
+                argon2::Error::MemoryTooMuch => write!(f, "memory cost is too large"),
+                argon2::Error::OutputTooShort => {
+                    write!(f, "requested key length is too short for this algorithm")
+                }
+                argon2::Error::OutputTooLong => {
+                    write!(f, "requested key length is too long for this algorithm")
+                }
+                argon2::Error::PwdTooLong => write!(f, "password is too long"),
+                argon2::Error::SaltTooShort => write!(
+                    f,
+                    "salt must be at least {} bytes long",
+                    argon2::MIN_SALT_LEN,
+                ),
+                argon2::Error::SaltTooLong => write!(f, "salt is too long"),
+                argon2::Error::SecretTooLong => write!(f, "secret is too long"),
+                argon2::Error::ThreadsTooFew => write!(f, "parallelism must be at least 1"),
+                argon2::Error::ThreadsTooMany => write!(f, "parallelism is too large"),
+                argon2::Error::TimeTooSmall => write!(f, "time cost must be at least 1"),
+                argon2::Error::VersionInvalid => write!(f, "unknown argon2 version"),
+            },
+        }
+    }
+}
+
+impl std::error::Error for KdfError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Argon2(e) => Some(e),
+            Self::LowEntropySalt => None,
+        }
+    }
+}
+
+// a heuristic floor so that salts like "aaaaaaaa" or all-zero bytes are flagged
+const MIN_SALT_UNIQUE_BYTES: usize = 4;
+
+// only a warning: keys for salts that are already in use must stay derivable,
+// and the length limits are enforced by each algorithm
+fn validate_salt(salt: &[u8]) -> Result<(), KdfError> {
+    let mut unique = [false; 256];
+    for &b in salt {
+        unique[b as usize] = true;
+    }
+    if unique.iter().filter(|&&v| v).count() < MIN_SALT_UNIQUE_BYTES {
+        return Err(KdfError::LowEntropySalt);
+    }
+    Ok(())
+}
+
+fn argon2_from_params(m_cost: u32, t_cost: u32, p_cost: u32) -> anyhow::Result<Argon2<'static>> {
+    let params = Params::new(m_cost, t_cost, p_cost, None)?;
+    Ok(Argon2::new(
+        argon2::Algorithm::Argon2id,
+        Version::V0x13,
+        params,
+    ))
+}
+
+// the parameters the app derives with; the pinned test vectors depend on them
+fn default_argon2() -> Argon2<'static> {
+    argon2_from_params(
+        Params::DEFAULT_M_COST,
+        Params::DEFAULT_T_COST,
+        Params::DEFAULT_P_COST,
+    )
+    .unwrap()
+}
+
 struct App {
     argon2: Argon2<'static>,
     password: String,
     password_validation: Option<Result<(), String>>,
     hash_actual: String,
     hash_expected: Option<PasswordHashString>,
+    // bumped when the stored hash changes behind HashExpectedInput
+    hash_expected_reload: u32,
     algorithm: Algorithm,
     salt: String,
     salt_validation: Result<(), String>,
-    key: Option<String>,
+    salt_warning: Option<String>,
+    key: Option<DerivedKey>,
+    key_timestamp: Option<String>,
+    storage_area: StorageArea,
+    theme: Theme,
 }
 
 impl yew::Component for App {
@@ -58,16 +444,25 @@ impl yew::Component for App {
 
     fn create(_: &yew::Context<Self>) -> Self {
         let mut s = Self {
-            argon2: Argon2::default(),
+            argon2: default_argon2(),
             password: String::new(),
             password_validation: None,
             hash_actual: String::new(),
             hash_expected: None,
+            hash_expected_reload: 0,
             algorithm: Algorithm::default(),
             salt: String::new(),
             salt_validation: Ok(()),
+            salt_warning: None,
             key: None,
+            key_timestamp: None,
+            storage_area: StorageArea::default(),
+            theme: LocalStorage::get::<String>(THEME_STORAGE_KEY)
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or_default(),
         };
+        s.load_fragment();
         s.update_password_validation();
         s.update_hash_actual();
         s.update_key();
@@ -86,13 +481,38 @@ impl yew::Component for App {
                 self.hash_expected = value;
                 self.update_password_validation();
             }
+            Message::HashActualRefresh => {
+                self.update_hash_actual();
+            }
             Message::Algorithm(value) => {
                 self.algorithm = value;
                 self.update_key();
+                self.store_fragment();
             }
             Message::Salt(value) => {
                 self.salt = value;
                 self.update_key();
+                self.store_fragment();
+            }
+            Message::StorageArea(value) => {
+                self.storage_area = value;
+            }
+            Message::Theme(value) => {
+                self.theme = value;
+                let _ = LocalStorage::set(THEME_STORAGE_KEY, self.theme.to_string());
+            }
+            Message::Config(value) => {
+                if let Some(hash) = &value.hash {
+                    self.storage_area.set(hash);
+                    self.hash_expected = PasswordHashString::new(hash).ok();
+                    self.update_password_validation();
+                    self.hash_expected_reload = self.hash_expected_reload.wrapping_add(1);
+                }
+                self.algorithm = value.algorithm;
+                self.update_key();
+                self.store_fragment();
+                self.theme = value.theme;
+                let _ = LocalStorage::set(THEME_STORAGE_KEY, self.theme.to_string());
             }
         }
         true
@@ -101,15 +521,27 @@ impl yew::Component for App {
     fn view(&self, ctx: &yew::Context<Self>) -> yew::Html {
         yew::html! {
             <main class="card">
+            <header class={yew::classes!("card-header", "d-flex", "justify-content-end", "gap-2")}>
+            <ConfigInput
+            algorithm={self.algorithm}
+            theme={self.theme}
+            storage_area={self.storage_area}
+            onchange={ctx.link().callback(Message::Config)} />
+            <ThemeToggle value={self.theme} onchange={ctx.link().callback(Message::Theme)} />
+            </header>
             <section class="card-body">
             <PasswordInput onchange={ctx.link().callback(Message::Password)} validation={self.password_validation.clone()} />
-            <HashActualOutput value={self.hash_actual.clone()} />
-            <HashExpectedInput onchange={ctx.link().callback(Message::HashExpected)} />
+            <HashActualOutput
+            value={self.hash_actual.clone()}
+            storage_area={self.storage_area}
+            onchange_storage_area={ctx.link().callback(Message::StorageArea)}
+            onrefresh={ctx.link().callback(|_| Message::HashActualRefresh)} />
+            <HashExpectedInput onchange={ctx.link().callback(Message::HashExpected)} storage_area={self.storage_area} reload={self.hash_expected_reload} />
             </section>
             <section class="card-body">
-            <AlgorithmInput onchange={ctx.link().callback(Message::Algorithm)} />
-            <SaltInput onchange={ctx.link().callback(Message::Salt)} validation={self.salt_validation.clone()} />
-            <KeyOutput value={self.key.clone()} />
+            <AlgorithmInput value={self.algorithm} onchange={ctx.link().callback(Message::Algorithm)} />
+            <SaltInput value={self.salt.clone()} onchange={ctx.link().callback(Message::Salt)} validation={self.salt_validation.clone()} warning={self.salt_warning.clone()} />
+            <KeyOutput value={self.key.clone()} timestamp={self.key_timestamp.clone()} />
             </section>
             </main>
         }
@@ -117,6 +549,34 @@ impl yew::Component for App {
 }
 
 impl App {
+    fn load_fragment(&mut self) {
+        let Some(hash) = web_sys::window().and_then(|window| window.location().hash().ok()) else {
+            return;
+        };
+        let fragment = Fragment::parse(&hash);
+        if let Some(algorithm) = fragment.algorithm {
+            self.algorithm = algorithm;
+        }
+        if let Some(salt) = fragment.salt {
+            self.salt = salt;
+        }
+    }
+
+    fn store_fragment(&self) {
+        let Some(history) = web_sys::window().and_then(|window| window.history().ok()) else {
+            return;
+        };
+        let fragment = Fragment {
+            algorithm: Some(self.algorithm),
+            salt: Some(self.salt.clone()),
+        };
+        let _ = history.replace_state_with_url(
+            &web_sys::wasm_bindgen::JsValue::NULL,
+            "",
+            Some(&fragment.to_string()),
+        );
+    }
+
     fn update_password_validation(&mut self) {
         self.password_validation = self.hash_expected.as_ref().map(|hash_expected| {
             self.argon2
@@ -134,34 +594,29 @@ impl App {
     }
 
     fn update_key(&mut self) {
-        match self.algorithm {
-            Algorithm::Argon2id256 => {
-                let mut key = [0u8; 32];
-                match self.argon2.hash_password_into(
-                    self.password.as_bytes(),
-                    self.salt.as_bytes(),
-                    &mut key,
-                ) {
-                    Ok(_) => {
-                        self.key = Some(BASE64_STANDARD.encode(key));
-                        self.salt_validation = Ok(());
-                    }
-                    Err(e @ argon2::Error::SaltTooShort | e @ argon2::Error::SaltTooLong) => {
-                        self.key = None;
-                        self.salt_validation = Err(e.to_string());
-                    }
-                    _ => unimplemented!(),
-                }
-            }
-            Algorithm::S2kSha256 => {
-                self.key = Some(BASE64_STANDARD.encode(s2k::<Sha256>(
-                    self.salt.as_bytes(),
-                    self.password.as_bytes(),
-                    65536,
-                )));
+        self.salt_warning = validate_salt(self.salt.as_bytes())
+            .err()
+            .map(|e| e.to_string());
+        match self
+            .algorithm
+            .key(&self.argon2, self.password.as_bytes(), self.salt.as_bytes())
+        {
+            Ok(key) => {
+                self.key = Some(key);
+                self.key_timestamp = Some(
+                    web_sys::js_sys::Date::new_0()
+                        .to_locale_time_string("en-US")
+                        .into(),
+                );
                 self.salt_validation = Ok(());
             }
-        };
+            // salt errors are the common case, but any KdfError has a readable message
+            Err(e) => {
+                self.key = None;
+                self.key_timestamp = None;
+                self.salt_validation = Err(e.to_string());
+            }
+        }
     }
 }
 
@@ -183,14 +638,41 @@ fn PasswordInput(props: &PasswordInputProps) -> yew::Html {
             callback.emit(value);
         }
     };
-    let onclick = {
+    let clear = {
         let setter = value.setter();
         let callback = props.onchange.clone();
-        move |_| {
+        move || {
             setter.set(String::new());
             callback.emit(String::new());
         }
     };
+    let onclick = {
+        let clear = clear.clone();
+        move |_| clear()
+    };
+    let node_ref = yew::use_node_ref();
+    // Escape also closes selects and autocomplete, so other form controls keep it
+    let escape = {
+        let node_ref = node_ref.clone();
+        move |_: &()| {
+            let listener = on_keydown(move |event| {
+                let focused =
+                    event.target().map(JsValue::from) == node_ref.get().map(JsValue::from);
+                if event.key() == "Escape" && (focused || !is_form_control(event)) {
+                    event.prevent_default();
+                    clear();
+                }
+            });
+            move || drop(listener)
+        }
+    };
+    yew::use_effect_with((), escape);
+    // the password is always the first thing to type
+    yew::use_effect_with(node_ref.clone(), |node_ref| {
+        if let Some(input) = node_ref.cast::<HtmlInputElement>() {
+            let _ = input.focus();
+        }
+    });
     let class_a = [
         Some("input-group"),
         props.validation.as_ref().map(|_| "has-validation"),
@@ -210,9 +692,9 @@ fn PasswordInput(props: &PasswordInputProps) -> yew::Html {
     yew::html! {
         <div class={class_a}>
         <label for="password" class={yew::classes!("input-group-prepend", "input-group-text")}>{"Password"}</label>
-        <input type="password" id="password" class={class_b} value={(*value).clone()} onchange={onchange} />
+        <input type="password" id="password" ref={node_ref} class={class_b} value={(*value).clone()} onchange={onchange} />
         <div class="input-group-append">
-        <button type="button" class={yew::classes!("btn", "btn-outline-secondary")} onclick={onclick}>
+        <button type="button" class={yew::classes!("btn", "btn-outline-secondary")} aria-label="clear the password" onclick={onclick}>
         <i class="bi bi-x-circle" />
         </button>
         </div>
@@ -226,22 +708,94 @@ fn PasswordInput(props: &PasswordInputProps) -> yew::Html {
 #[derive(PartialEq, yew::Properties)]
 struct HashActualOutputProps {
     value: String,
+    storage_area: StorageArea,
+    onchange_storage_area: yew::Callback<StorageArea>,
+    onrefresh: yew::Callback<()>,
 }
 
 #[yew::function_component]
 fn HashActualOutput(props: &HashActualOutputProps) -> yew::Html {
     let onclick = {
         let value = props.value.clone();
+        let storage_area = props.storage_area;
+        move |_| storage_area.set(&value)
+    };
+    // the password input only commits on change, so Ctrl+Enter blurs it first
+    // and saves once the hash of the committed password comes back as a prop
+    let save_pending = yew::use_state(|| false);
+    let setter = save_pending.setter();
+    yew::use_effect_with(
+        (props.value.clone(), props.storage_area, *save_pending),
+        move |(value, storage_area, pending)| {
+            if *pending {
+                storage_area.set(value);
+                setter.set(false);
+            }
+        },
+    );
+    let save = save_pending.setter();
+    yew::use_effect_with((), move |_| {
+        let listener = on_keydown(move |event| {
+            if event.key() == "Enter" && event.ctrl_key() {
+                event.prevent_default();
+                if let Some(element) = web_sys::window()
+                    .and_then(|window| window.document())
+                    .and_then(|document| document.active_element())
+                    .and_then(|element| element.dyn_into::<HtmlElement>().ok())
+                {
+                    let _ = element.blur();
+                }
+                save.set(true);
+            }
+        });
+        move || drop(listener)
+    });
+    let onclick_storage_area = {
+        let callback = props.onchange_storage_area.clone();
+        let storage_area = props.storage_area;
         move |_| {
-            let _ = LocalStorage::set(STORAGE_KEY, &value);
+            callback.emit(match storage_area {
+                StorageArea::Local => StorageArea::Session,
+                StorageArea::Session => StorageArea::Local,
+            })
         }
     };
+    let onclick_refresh = {
+        let callback = props.onrefresh.clone();
+        move |_| callback.emit(())
+    };
     yew::html! {
         <div class="input-group">
         <label for="hash-actual" class={yew::classes!("input-group-prepend", "input-group-text")}>{"Hash (actual)"}</label>
         <input type="text" id="hash-actual" class={yew::classes!("form-control")} value={props.value.clone()} readonly=true />
         <div class="input-group-append">
-        <button type="button" class={yew::classes!("btn", "btn-outline-secondary")} onclick={onclick}>
+        <button
+        type="button"
+        id="hash-actual:refresh"
+        class={yew::classes!("btn", "btn-outline-secondary")}
+        title="hash again with a new salt"
+        aria-label="hash again with a new salt"
+        onclick={onclick_refresh}>
+        <i class="bi bi-arrow-clockwise" />
+        </button>
+        <button
+        type="button"
+        id="storage:session"
+        class={yew::classes!("btn", "btn-outline-secondary")}
+        title={match props.storage_area {
+            StorageArea::Local => "local storage",
+            StorageArea::Session => "session storage",
+        }}
+        aria-label="keep the hash in session storage"
+        aria-pressed={(props.storage_area == StorageArea::Session).to_string()}
+        onclick={onclick_storage_area}>
+        if props.storage_area == StorageArea::Session {
+            <i class="bi bi-hourglass-split" />
+        } else {
+            <i class="bi bi-hdd" />
+        }
+        </button>
+        <button type="button" class={yew::classes!("btn", "btn-outline-secondary")} aria-label="save the hash" onclick={onclick}>
         <i class="bi bi-file-earmark-arrow-up" />
         </button>
         </div>
@@ -252,6 +806,8 @@ fn HashActualOutput(props: &HashActualOutputProps) -> yew::Html {
 #[derive(PartialEq, yew::Properties)]
 struct HashExpectedInputProps {
     onchange: yew::Callback<Option<PasswordHashString>>,
+    storage_area: StorageArea,
+    reload: u32,
 }
 
 #[yew::function_component]
@@ -277,12 +833,13 @@ fn HashExpectedInput(props: &HashExpectedInputProps) -> yew::Html {
             }
         }
     };
-    let onclick = {
+    let load = {
         let setter = value.setter();
         let validation_setter = validation.setter();
         let callback = props.onchange.clone();
-        move |_| {
-            let value = LocalStorage::get::<String>(STORAGE_KEY).unwrap_or_default();
+        let storage_area = props.storage_area;
+        move || {
+            let value = storage_area.get().unwrap_or_default();
             setter.set(value.clone());
             match PasswordHashString::new(&value) {
                 Ok(v) => {
@@ -296,6 +853,46 @@ fn HashExpectedInput(props: &HashExpectedInputProps) -> yew::Html {
             }
         }
     };
+    let onclick = {
+        let load = load.clone();
+        move |_| load()
+    };
+    // the parent already holds the new hash, so only the field is refreshed
+    let reload = {
+        let setter = value.setter();
+        let validation_setter = validation.setter();
+        let storage_area = props.storage_area;
+        move |reload: &u32| {
+            if *reload != 0 {
+                let value = storage_area.get().unwrap_or_default();
+                validation_setter.set(PasswordHashString::new(&value).map(|_| ()));
+                setter.set(value);
+            }
+        }
+    };
+    yew::use_effect_with(props.reload, reload);
+    // Ctrl+L loads the stored hash, e.g. one saved from another tab
+    yew::use_effect_with(props.storage_area, move |_| {
+        let listener = on_keydown(move |event| {
+            if event.key().eq_ignore_ascii_case("l") && event.ctrl_key() {
+                event.prevent_default();
+                load();
+            }
+        });
+        move || drop(listener)
+    });
+    let onclick_clear = {
+        let setter = value.setter();
+        let validation_setter = validation.setter();
+        let callback = props.onchange.clone();
+        let storage_area = props.storage_area;
+        move |_| {
+            storage_area.delete();
+            setter.set(String::new());
+            validation_setter.set(PasswordHashString::new("").map(|_| ()));
+            callback.emit(None);
+        }
+    };
     let class = [
         Some("form-control"),
         match &*validation {
@@ -310,9 +907,18 @@ fn HashExpectedInput(props: &HashExpectedInputProps) -> yew::Html {
         <label for="hash-expected" class={yew::classes!("input-group-prepend", "input-group-text")}>{"Hash (expected)"}</label>
         <input type="text" id="hash-expected" class={class} value={(*value).clone()} onchange={onchange} />
         <div class="input-group-append">
-        <button type="button" class={yew::classes!("btn", "btn-outline-secondary")} onclick={onclick}>
+        <button type="button" class={yew::classes!("btn", "btn-outline-secondary")} aria-label="load the stored hash" onclick={onclick}>
         <i class="bi bi-file-earmark-arrow-down" />
         </button>
+        <button
+        type="button"
+        id="hash-expected:clear"
+        class={yew::classes!("btn", "btn-outline-secondary")}
+        title="clear the stored hash"
+        aria-label="clear the stored hash"
+        onclick={onclick_clear}>
+        <i class="bi bi-trash" />
+        </button>
         </div>
         if let Err(e) = &*validation {
             <div class={yew::classes!("invalid-feedback")}>{e.to_string()}</div>
@@ -323,14 +929,13 @@ fn HashExpectedInput(props: &HashExpectedInputProps) -> yew::Html {
 
 #[derive(PartialEq, yew::Properties)]
 struct AlgorithmInputProps {
+    value: Algorithm,
     onchange: yew::Callback<Algorithm>,
 }
 
 #[yew::function_component]
 fn AlgorithmInput(props: &AlgorithmInputProps) -> yew::Html {
-    let value = yew::use_state(Algorithm::default);
     let onchange = {
-        let setter = value.setter();
         let callback = props.onchange.clone();
         move |event: Event| {
             let value = event
@@ -339,14 +944,13 @@ fn AlgorithmInput(props: &AlgorithmInputProps) -> yew::Html {
                 .value()
                 .parse()
                 .unwrap();
-            setter.set(value);
             callback.emit(value);
         }
     };
     let options = Algorithm::iter()
         .map(|v| {
             yew::html! {
-                <option value={v.to_string()} selected={v == *value}>{v.get_message()}</option>
+                <option value={v.to_string()} selected={v == props.value}>{v.get_message()}</option>
             }
         })
         .collect::<Vec<_>>();
@@ -360,19 +964,18 @@ fn AlgorithmInput(props: &AlgorithmInputProps) -> yew::Html {
 
 #[derive(PartialEq, yew::Properties)]
 struct SaltInputProps {
+    value: String,
     onchange: yew::Callback<String>,
     validation: Option<Result<(), String>>,
+    warning: Option<String>,
 }
 
 #[yew::function_component]
 fn SaltInput(props: &SaltInputProps) -> yew::Html {
-    let value = yew::use_state(String::new);
     let onchange = {
-        let setter = value.setter();
         let callback = props.onchange.clone();
         move |event: Event| {
             let value = event.target_dyn_into::<HtmlInputElement>().unwrap().value();
-            setter.set(value.clone());
             callback.emit(value);
         }
     };
@@ -393,38 +996,48 @@ fn SaltInput(props: &SaltInputProps) -> yew::Html {
     .into_iter()
     .collect::<yew::Classes>();
     yew::html! {
+        <>
         <div class={class_a}>
         <label for="salt" class={yew::classes!("input-group-prepend", "input-group-text")}>{"Salt"}</label>
-        <input type="salt" id="text" class={class_b} value={(*value).clone()} onchange={onchange} />
+        <input type="text" id="salt" class={class_b} value={props.value.clone()} onchange={onchange} />
         if let Some(Err(e)) = &props.validation {
             <div class={yew::classes!("invalid-feedback")}>{e.to_string()}</div>
         }
         </div>
+        if let Some(warning) = &props.warning {
+            <div id="salt:warning" class={yew::classes!("form-text", "text-warning")}>{warning}</div>
+        }
+        </>
     }
 }
 
 #[derive(PartialEq, yew::Properties)]
 struct KeyOutputProps {
-    value: Option<String>,
+    value: Option<DerivedKey>,
+    timestamp: Option<String>,
 }
 
 #[yew::function_component]
 fn KeyOutput(props: &KeyOutputProps) -> yew::Html {
     let visible = yew::use_state(|| false);
-    let onclick_visible = {
-        let visible = visible.clone();
-        move |_| visible.set(!*visible)
-    };
     let clipboard =
         yew::use_state(|| web_sys::window().map(|window| window.navigator().clipboard()));
     let clipboard_state = yew::use_state(|| false);
-    let onclick_clipboard = {
+    // dropping the pending timeout cancels it, so repeated copies keep the icon
+    let clipboard_reset = yew::use_mut_ref(|| None::<Timeout>);
+    let clipboard_timeout = yew::use_state(|| {
+        let search = web_sys::window().and_then(|window| window.location().search().ok());
+        copy_timeout(search.as_deref().unwrap_or_default())
+    });
+    let copy = {
         let clipboard = clipboard.clone();
         let clipboard_state = clipboard_state.setter();
+        let timeout = u32::try_from(clipboard_timeout.as_millis()).unwrap_or(u32::MAX);
         let value = props.value.clone();
-        move |_| {
+        move || {
             let clipboard = clipboard.clone();
             let clipboard_state = clipboard_state.clone();
+            let clipboard_reset = clipboard_reset.clone();
             let value = value.clone();
             wasm_bindgen_futures::spawn_local(
                 async move {
@@ -433,25 +1046,55 @@ fn KeyOutput(props: &KeyOutputProps) -> yew::Html {
                         .await
                         .ok()?;
                     clipboard_state.set(true);
-                    gloo::timers::future::sleep(Duration::from_secs(1)).await;
-                    clipboard_state.set(false);
+                    *clipboard_reset.borrow_mut() =
+                        Some(Timeout::new(timeout, move || clipboard_state.set(false)));
                     Some(())
                 }
                 .map(|_| ()),
             );
         }
     };
+    let copy_on_reveal = yew::use_state(|| {
+        let search = web_sys::window().and_then(|window| window.location().search().ok());
+        copy_on_reveal(search.as_deref().unwrap_or_default())
+    });
+    let onclick_visible = {
+        let visible = visible.clone();
+        let copy = copy.clone();
+        move |_| {
+            let (value, copy_now) = toggle_visible(*visible, *copy_on_reveal);
+            visible.set(value);
+            if copy_now {
+                copy();
+            }
+        }
+    };
+    let onclick_clipboard = {
+        let copy = copy.clone();
+        move |_| copy()
+    };
+    yew::use_effect_with(props.value.clone(), move |value| {
+        let enabled = value.is_some();
+        let listener = on_keydown(move |event| {
+            if enabled && event.key() == "Enter" && !event.ctrl_key() && !is_form_control(event) {
+                event.prevent_default();
+                copy();
+            }
+        });
+        move || drop(listener)
+    });
     yew::html! {
+        <>
         <div class="input-group">
         <label for="key" class={yew::classes!("input-group-prepend", "input-group-text")}>{"Key"}</label>
         <input
         id="key"
         type={if *visible { "text" } else { "password" }}
         class={yew::classes!("form-control")}
-        value={props.value.clone().unwrap_or_default()}
+        value={props.value.as_deref().unwrap_or_default().to_owned()}
         readonly=true disabled={props.value.is_none()} />
         <div class="input-group-append">
-        <button type="button" class={yew::classes!("btn", "btn-outline-secondary")} onclick={onclick_visible} disabled={props.value.is_none()}>
+        <button type="button" class={yew::classes!("btn", "btn-outline-secondary")} aria-label="show or hide the key" onclick={onclick_visible} disabled={props.value.is_none()}>
         if *visible {
             <i class="bi bi-eye" />
         } else {
@@ -459,7 +1102,7 @@ fn KeyOutput(props: &KeyOutputProps) -> yew::Html {
         }
         </button>
         if clipboard.is_some() {
-            <button type="button" class={yew::classes!("btn", "btn-outline-secondary")} onclick={onclick_clipboard} disabled={props.value.is_none()}>
+            <button type="button" class={yew::classes!("btn", "btn-outline-secondary")} aria-label="copy the key" onclick={onclick_clipboard} disabled={props.value.is_none()}>
             if *clipboard_state {
                 <i class="bi bi-clipboard-check" />
             } else {
@@ -469,6 +1112,241 @@ fn KeyOutput(props: &KeyOutputProps) -> yew::Html {
         }
         </div>
         </div>
+        <small id="key:timestamp" class={yew::classes!("form-text")}>
+        if let Some(timestamp) = &props.timestamp {
+            {format!("derived at {timestamp}")}
+        }
+        </small>
+        </>
+    }
+}
+
+// how long the copy button shows its check mark, from `?timeout=<seconds>`
+fn copy_timeout(search: &str) -> Duration {
+    form_urlencoded::parse(search.trim_start_matches('?').as_bytes())
+        .find(|(k, _)| k == "timeout")
+        .and_then(|(_, v)| v.parse().ok())
+        .map_or(Duration::from_secs(1), Duration::from_secs)
+}
+
+// `?copy_on_reveal` copies the key as soon as it is shown, so that it does not
+// have to stay on screen while it is selected
+fn copy_on_reveal(search: &str) -> bool {
+    form_urlencoded::parse(search.trim_start_matches('?').as_bytes())
+        .any(|(k, v)| k == "copy_on_reveal" && matches!(&*v, "" | "1" | "true"))
+}
+
+// returns the new visibility and whether the key should be copied now
+fn toggle_visible(visible: bool, copy_on_reveal: bool) -> (bool, bool) {
+    (!visible, !visible && copy_on_reveal)
+}
+
+fn on_keydown<F>(f: F) -> Option<EventListener>
+where
+    F: Fn(&KeyboardEvent) + 'static,
+{
+    let document = web_sys::window()?.document()?;
+    Some(EventListener::new(&document, "keydown", move |event| {
+        if let Some(event) = event.dyn_ref() {
+            f(event);
+        }
+    }))
+}
+
+// Enter already means something inside form controls
+fn is_form_control(event: &KeyboardEvent) -> bool {
+    event
+        .target()
+        .and_then(|target| target.dyn_into::<Element>().ok())
+        .is_some_and(|element| {
+            matches!(
+                &*element.tag_name(),
+                "BUTTON" | "INPUT" | "SELECT" | "TEXTAREA"
+            )
+        })
+}
+
+#[derive(PartialEq, yew::Properties)]
+struct ConfigInputProps {
+    algorithm: Algorithm,
+    theme: Theme,
+    storage_area: StorageArea,
+    onchange: yew::Callback<Config>,
+}
+
+#[yew::function_component]
+fn ConfigInput(props: &ConfigInputProps) -> yew::Html {
+    let validation = yew::use_state(|| Ok::<_, String>(()));
+    let onclick = {
+        let algorithm = props.algorithm;
+        let theme = props.theme;
+        let storage_area = props.storage_area;
+        move |_| {
+            let config = Config {
+                hash: storage_area.get(),
+                algorithm,
+                theme,
+            };
+            download(
+                concat!(env!("CARGO_BIN_NAME"), ".json"),
+                &serde_json::to_string_pretty(&config).unwrap(),
+            );
+        }
+    };
+    let onchange = {
+        let validation_setter = validation.setter();
+        let callback = props.onchange.clone();
+        move |event: Event| {
+            let input = event.target_dyn_into::<HtmlInputElement>().unwrap();
+            let Some(file) = input.files().and_then(|files| files.get(0)) else {
+                return;
+            };
+            // allow importing the same file again
+            input.set_value("");
+            let validation_setter = validation_setter.clone();
+            let callback = callback.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match gloo::file::futures::read_as_text(&gloo::file::File::from(file))
+                    .await
+                    .map_err(|e| e.to_string())
+                    .and_then(|value| Config::parse(&value))
+                {
+                    Ok(v) => {
+                        validation_setter.set(Ok(()));
+                        callback.emit(v);
+                    }
+                    Err(e) => validation_setter.set(Err(e)),
+                }
+            });
+        }
+    };
+    // a button rather than a label so that the import is in the tab order
+    let node_ref = yew::use_node_ref();
+    let onclick_import = {
+        let node_ref = node_ref.clone();
+        move |_| {
+            if let Some(input) = node_ref.cast::<HtmlInputElement>() {
+                input.click();
+            }
+        }
+    };
+    let class = [
+        Some("btn"),
+        match &*validation {
+            Ok(_) => Some("btn-outline-secondary"),
+            Err(_) => Some("btn-outline-danger"),
+        },
+    ]
+    .into_iter()
+    .collect::<yew::Classes>();
+    let title = validation
+        .as_ref()
+        .err()
+        .cloned()
+        .unwrap_or("import".to_owned());
+    yew::html! {
+        <div class="btn-group">
+        <button type="button" id="config:export" class={yew::classes!("btn", "btn-outline-secondary")} title="export" aria-label="export the config" onclick={onclick}>
+        <i class="bi bi-download" />
+        </button>
+        <button type="button" class={class} title={title.clone()} aria-label={title} onclick={onclick_import}>
+        <i class="bi bi-upload" />
+        </button>
+        <input type="file" id="config:import" ref={node_ref} class={yew::classes!("d-none")} accept=".json" onchange={onchange} />
+        </div>
+    }
+}
+
+fn download(name: &str, value: &str) -> Option<()> {
+    let element = web_sys::window()?
+        .document()?
+        .create_element("a")
+        .ok()?
+        .dyn_into::<HtmlAnchorElement>()
+        .ok()?;
+    element.set_href(&format!(
+        "data:application/json;base64,{}",
+        BASE64_STANDARD.encode(value),
+    ));
+    element.set_download(name);
+    element.click();
+    Some(())
+}
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    serde::Deserialize,
+    serde::Serialize,
+    strum::Display,
+    strum::EnumString,
+)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+enum Theme {
+    #[default]
+    Auto,
+    Dark,
+    Light,
+}
+
+#[derive(PartialEq, yew::Properties)]
+struct ThemeToggleProps {
+    value: Theme,
+    onchange: yew::Callback<Theme>,
+}
+
+#[yew::function_component]
+fn ThemeToggle(props: &ThemeToggleProps) -> yew::Html {
+    yew::use_effect_with(props.value, |&value| {
+        let media = web_sys::window()
+            .and_then(|window| window.match_media("(prefers-color-scheme: dark)").ok())
+            .flatten();
+        let apply = {
+            let media = media.clone();
+            move || apply_theme(value, media.as_ref())
+        };
+        apply();
+        let listener = media.map(|media| EventListener::new(&media, "change", move |_| apply()));
+        move || drop(listener)
+    });
+    let onclick = {
+        let callback = props.onchange.clone();
+        let value = props.value;
+        move |_| {
+            callback.emit(match value {
+                Theme::Auto => Theme::Dark,
+                Theme::Dark => Theme::Light,
+                Theme::Light => Theme::Auto,
+            })
+        }
+    };
+    yew::html! {
+        <button type="button" id="theme:toggle" class={yew::classes!("btn", "btn-outline-secondary")} title={props.value.to_string()} aria-label={format!("change the theme ({})", props.value)} onclick={onclick}>
+        {match props.value {
+            Theme::Auto => yew::html! { <i class="bi bi-circle-half" /> },
+            Theme::Dark => yew::html! { <i class="bi bi-moon-stars" /> },
+            Theme::Light => yew::html! { <i class="bi bi-sun" /> },
+        }}
+        </button>
+    }
+}
+
+fn apply_theme(theme: Theme, media: Option<&MediaQueryList>) {
+    let dark = match theme {
+        Theme::Auto => media.is_some_and(MediaQueryList::matches),
+        Theme::Dark => true,
+        Theme::Light => false,
+    };
+    if let Some(element) = web_sys::window()
+        .and_then(|window| window.document())
+        .and_then(|document| document.document_element())
+    {
+        // https://getbootstrap.com/docs/5.3/customize/color-modes/
+        let _ = element.set_attribute("data-bs-theme", if dark { "dark" } else { "light" });
     }
 }
 
@@ -483,10 +1361,507 @@ where
     hasher.finalize()
 }
 
+// fills `out` in place, so the caller decides how the output is wiped
+fn derive_subkey(context: &str, key_material: &[u8], out: &mut [u8]) {
+    blake3::Hasher::new_derive_key(context)
+        .update(key_material)
+        .finalize_xof()
+        .fill(out);
+}
+
+// https://cr.yp.to/ecdh.html
+fn clamp_x25519(key: &mut [u8; 32]) {
+    key[0] &= 248;
+    key[31] &= 127;
+    key[31] |= 64;
+}
+
+// treats key as a big-endian integer and takes its digits in base words.len()
+fn encode_words<'a>(key: &[u8], words: &[&'a str], count: usize) -> Vec<&'a str> {
+    // nothing to pick from; also avoids dividing by zero below
+    if words.is_empty() {
+        return Vec::new();
+    }
+    let n = words.len() as u32;
+    let mut key = Zeroizing::new(key.to_vec());
+    (0..count)
+        .map(|_| {
+            let mut r = 0;
+            for b in key.iter_mut() {
+                let v = (r << 8) | *b as u32;
+                *b = (v / n) as u8;
+                r = v % n;
+            }
+            words[r as usize]
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
+    use super::{
+        argon2_from_params, copy_on_reveal, copy_timeout, default_argon2, toggle_visible,
+        Algorithm, Config, DerivedKey, Fragment, KdfError, Theme, EFF_LARGE_WORDLIST,
+    };
+    use age::secrecy::ExposeSecret;
+    use argon2::{Argon2, Params};
+    use base64::prelude::{Engine, BASE64_STANDARD};
     use hex_literal::hex;
-    use sha2::Sha256;
+    use hmac::digest::KeyInit;
+    use hmac::{Hmac, Mac};
+    use proptest::prelude::*;
+    use sha2::{Sha256, Sha512};
+    use std::time::{Duration, Instant};
+    use strum::IntoEnumIterator;
+    use zeroize::Zeroize;
+
+    // keeps the tests fast; the parameters do not matter for the encoding
+    fn argon2() -> Argon2<'static> {
+        argon2_from_params(Params::MIN_M_COST, 1, 1).unwrap()
+    }
+
+    #[test]
+    fn test_kdf_error() {
+        for (error, expected) in [
+            (argon2::Error::AdTooLong, "associated data is too long"),
+            (argon2::Error::AlgorithmInvalid, "unknown argon2 algorithm"),
+            (
+                argon2::Error::B64Encoding(
+                    argon2::password_hash::errors::B64Error::InvalidEncoding,
+                ),
+                "invalid base64 encoding: invalid Base64 encoding",
+            ),
+            (argon2::Error::KeyIdTooLong, "key ID is too long"),
+            (argon2::Error::MemoryTooLittle, "memory cost is too small"),
+            (argon2::Error::MemoryTooMuch, "memory cost is too large"),
+            (
+                argon2::Error::OutputTooShort,
+                "requested key length is too short for this algorithm",
+            ),
+            (
+                argon2::Error::OutputTooLong,
+                "requested key length is too long for this algorithm",
+            ),
+            (argon2::Error::PwdTooLong, "password is too long"),
+            (
+                argon2::Error::SaltTooShort,
+                "salt must be at least 8 bytes long",
+            ),
+            (argon2::Error::SaltTooLong, "salt is too long"),
+            (argon2::Error::SecretTooLong, "secret is too long"),
+            (
+                argon2::Error::ThreadsTooFew,
+                "parallelism must be at least 1",
+            ),
+            (argon2::Error::ThreadsTooMany, "parallelism is too large"),
+            (argon2::Error::TimeTooSmall, "time cost must be at least 1"),
+            (argon2::Error::VersionInvalid, "unknown argon2 version"),
+        ] {
+            assert_eq!(KdfError::Argon2(error).to_string(), expected, "{error:?}");
+        }
+        assert_eq!(
+            KdfError::LowEntropySalt.to_string(),
+            "salt should contain at least 4 distinct bytes",
+        );
+    }
+
+    #[test]
+    fn test_argon2_from_params() {
+        assert_eq!(default_argon2().params(), Argon2::default().params());
+        assert!(argon2_from_params(Params::MIN_M_COST, 1, 1).is_ok());
+        assert!(argon2_from_params(Params::MIN_M_COST - 1, 1, 1).is_err());
+        assert!(argon2_from_params(Params::DEFAULT_M_COST, 0, 1).is_err());
+        assert!(argon2_from_params(Params::DEFAULT_M_COST, 1, 0).is_err());
+        // m_cost must be at least 8 KiB per lane
+        assert!(argon2_from_params(16, 1, 4).is_err());
+    }
+
+    #[test]
+    fn test_validate_salt() {
+        assert!(matches!(
+            super::validate_salt(&[0; 8]),
+            Err(KdfError::LowEntropySalt),
+        ));
+        assert!(matches!(
+            super::validate_salt(b"abcabcab"),
+            Err(KdfError::LowEntropySalt),
+        ));
+        assert!(super::validate_salt(b"abcdabcd").is_ok());
+        // the length is left to the algorithms
+        assert!(super::validate_salt(b"abcd").is_ok());
+        // a weak salt is only a warning and still derives a key
+        assert!(Algorithm::Argon2id256
+            .key(&argon2(), b"password", &[0; 8])
+            .is_ok());
+        assert!(matches!(
+            Algorithm::Argon2id256.key(&argon2(), b"password", b"abcdabc"),
+            Err(KdfError::Argon2(argon2::Error::SaltTooShort)),
+        ));
+        // s2k never had a length requirement
+        assert!(Algorithm::S2kSha256
+            .key(&argon2(), b"password", b"")
+            .is_ok());
+    }
+
+    #[test]
+    fn test_algorithm() {
+        // AlgorithmInput relies on this round trip for <option value>
+        for algorithm in Algorithm::iter() {
+            assert_eq!(algorithm.to_string().parse(), Ok(algorithm));
+        }
+        assert_eq!("Argon2id256".parse(), Ok(Algorithm::Argon2id256));
+        // App::create starts from this when the URL fragment has no algorithm
+        assert_eq!(Algorithm::default(), Algorithm::Argon2id256);
+        let algorithms = Algorithm::iter().collect::<std::collections::HashSet<_>>();
+        assert_eq!(algorithms.len(), Algorithm::iter().count());
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(1000))]
+
+        // catches randomness (e.g. OsRng) leaking into the derivation
+        #[test]
+        fn test_key_deterministic(
+            password in prop::collection::vec(any::<u8>(), 0..64),
+            salt in prop::collection::vec(any::<u8>(), 8..32),
+            index in any::<prop::sample::Index>(),
+            password_index in any::<prop::sample::Index>(),
+        ) {
+            let argon2 = argon2();
+            let mut salt_changed = salt.clone();
+            salt_changed[index.index(salt.len())] ^= 1;
+            // an empty password has no byte to flip
+            let password_changed = (!password.is_empty()).then(|| {
+                let mut password_changed = password.clone();
+                password_changed[password_index.index(password.len())] ^= 1;
+                password_changed
+            });
+            for algorithm in Algorithm::iter() {
+                let key = algorithm.key(&argon2, &password, &salt).unwrap();
+                prop_assert_eq!(&algorithm.key(&argon2, &password, &salt).unwrap(), &key);
+                prop_assert_ne!(&algorithm.key(&argon2, &password, &salt_changed).unwrap(), &key);
+                if let Some(password_changed) = &password_changed {
+                    prop_assert_ne!(&algorithm.key(&argon2, password_changed, &salt).unwrap(), &key);
+                }
+            }
+        }
+    }
+
+    // pinned with the default parameters to catch accidental encoding changes;
+    // slow, so run with `cargo test -- --ignored`
+    #[test]
+    #[ignore]
+    fn test_key_vectors() {
+        for algorithm in Algorithm::iter() {
+            let expected = match algorithm {
+                Algorithm::Argon2id128 => "KoPe2qQAeaWcNuR5K12fiw==",
+                Algorithm::Argon2id256 => "aspjTt8Dpt/J9KrhRn/w5gg+4PHyMuTiVNEl03PyDGI=",
+                Algorithm::Argon2id384 => "QVINpQiPyODrzS7dUzs8xR2eDPm1DOG2Fpx2p6mCKfMu+WaTLpsZpO/Fb1vOJUt2",
+                Algorithm::S2kSha256 => "z6UFEBVyS/1gmNREQBp9pJMseI9zrjbjwGwnwBQkxNE=",
+                Algorithm::Diceware5 => "oak untaken oozy overprice knee",
+                Algorithm::Diceware6 => "lend tightrope imprudent reissue undocked smokiness",
+                Algorithm::WireGuard => "IGybH3jgpaDI0x7h0vZU7h4lX9416G3HYuKUVx+Df1M=",
+                Algorithm::Age => {
+                    "AGE-SECRET-KEY-1FZC0AJ08Y8SLH2EQD62SXP5SPU0C4MMCQJ37C3PSHNM6WXQHW3YS8V64T8"
+                }
+                Algorithm::Blake3_256 => "ICOOPNc5XsfOAVSp1gn8yciZ9Vqu0sBH19wrFXaXOhM=",
+                Algorithm::Blake3_512 => "h6ctSGv5OApAXbQTq+liI11L4CLupGOARlW/bqkNF9x/XYDBgnxPkgiAySJamFMsFLyhKwZw7IRx9lK/MgMDMg==",
+                Algorithm::SshEd25519 => "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIHThAwqnMlxaNAfzhVUYOvjxGOceKjtmzNHLmzggBOuz s2k-derived",
+                Algorithm::HmacSha256Key => "KFF37THRBA6V7BSASMWQKSAEVXXIJ3SY3I7KOU6FRXBAOTXUVQCQ",
+                Algorithm::HmacSha512Key => "AGUM2YZ2WL6LIJJONPSAOIEAID3MZRSEQ5PUNJTSJ3WXFX5ALWJPQ4CMGWDKR6FG7MSMBBTZPNYCLVHKQTAO2Y4GVC327AM4S72MUKI",
+            };
+            assert_eq!(
+                &*algorithm
+                    .key(&default_argon2(), b"password", b"saltsalt")
+                    .unwrap(),
+                expected,
+                "{algorithm:?}",
+            );
+        }
+    }
+
+    #[test]
+    fn test_derived_key() {
+        let key = DerivedKey::new("secret");
+        assert_eq!(format!("{key:?}"), "DerivedKey(***)");
+        assert_eq!(key.to_string(), "secret");
+        assert_eq!(&*key, "secret");
+        let mut key = key;
+        key.zeroize();
+        assert_eq!(&*key, "");
+    }
+
+    // guards against someone lowering the costs to speed up CI. timing tests
+    // are fragile (debug builds, shared runners, throttling), so instead of a
+    // wall-clock floor this compares against the cheapest parameters on the
+    // same machine; run with `cargo test -- --ignored test_kdf_min_duration`
+    #[test]
+    #[ignore]
+    fn test_kdf_min_duration() {
+        let time = |argon2: &Argon2| {
+            let start = Instant::now();
+            Algorithm::Argon2id256
+                .key(argon2, b"password", b"saltsalt")
+                .unwrap();
+            start.elapsed()
+        };
+        let cheap = (0..10).map(|_| time(&argon2())).max().unwrap();
+        let default = time(&default_argon2());
+        assert!(default >= cheap * 100, "{default:?} vs {cheap:?}");
+    }
+
+    #[test]
+    fn test_config() {
+        let config = Config {
+            hash: Some("$argon2id$v=19$m=19456,t=2,p=1$c2FsdHNhbHQ$VKA6bwhXiBdULj1DIIEOOZkcTUJnZppWhWI3c8MgsKQ".to_owned()),
+            algorithm: Algorithm::S2kSha256,
+            theme: Theme::Dark,
+        };
+        let value = serde_json::to_string(&config).unwrap();
+        assert_eq!(
+            value,
+            r#"{"hash":"$argon2id$v=19$m=19456,t=2,p=1$c2FsdHNhbHQ$VKA6bwhXiBdULj1DIIEOOZkcTUJnZppWhWI3c8MgsKQ","algorithm":"S2kSha256","theme":"dark"}"#,
+        );
+        assert_eq!(Config::parse(&value), Ok(config));
+        assert!(
+            Config::parse(r#"{"hash":"invalid","algorithm":"S2kSha256","theme":"dark"}"#).is_err()
+        );
+        assert!(Config::parse(r#"{"hash":null,"algorithm":"unknown","theme":"dark"}"#).is_err());
+    }
+
+    #[test]
+    fn test_diceware() {
+        let argon2 = argon2();
+        let key = Algorithm::Diceware5
+            .key(&argon2, b"password", b"saltsalt")
+            .unwrap();
+        let words = key.split(' ').collect::<Vec<_>>();
+        assert_eq!(words.len(), 5);
+        assert!(words.iter().all(|w| EFF_LARGE_WORDLIST.contains(w)));
+        // not the plain argon2id key read as words
+        let mut argon2id = [0u8; 32];
+        argon2
+            .hash_password_into(b"password", b"saltsalt", &mut argon2id)
+            .unwrap();
+        assert_ne!(
+            words,
+            super::encode_words(&argon2id, &EFF_LARGE_WORDLIST, 5)
+        );
+        // nor the first five of the six words
+        let key6 = Algorithm::Diceware6
+            .key(&argon2, b"password", b"saltsalt")
+            .unwrap();
+        assert!(!key6.starts_with(&*key));
+    }
+
+    #[test]
+    fn test_wireguard() {
+        let argon2 = argon2();
+        let key = Algorithm::WireGuard
+            .key(&argon2, b"password", b"saltsalt")
+            .unwrap();
+        assert_eq!(key.len(), 44);
+        let key = BASE64_STANDARD.decode(key.as_bytes()).unwrap();
+        assert_eq!(key.len(), 32);
+        assert_eq!(key[0] & 7, 0);
+        assert_eq!(key[31] & 192, 64);
+        // not a clamped copy of the plain argon2id key
+        let mut argon2id = [0u8; 32];
+        argon2
+            .hash_password_into(b"password", b"saltsalt", &mut argon2id)
+            .unwrap();
+        assert_ne!(key[1..31], argon2id[1..31]);
+    }
+
+    #[test]
+    fn test_age() {
+        let key = Algorithm::Age
+            .key(&argon2(), b"password", b"saltsalt")
+            .unwrap();
+        assert!(key.starts_with("AGE-SECRET-KEY-1"));
+        let identity = key.parse::<age::x25519::Identity>().unwrap();
+        assert_eq!(identity.to_string().expose_secret(), &*key);
+        assert_eq!(
+            identity.to_public().to_string(),
+            "age1a8slh84dlnragn5k2ac432r4knxyxvf53wlg4kjx6ez7wht3d4ssl3zv7t",
+        );
+        let (_, scalar) = bech32::decode(&key).unwrap();
+        let wireguard = Algorithm::WireGuard
+            .key(&argon2(), b"password", b"saltsalt")
+            .unwrap();
+        assert_ne!(
+            scalar,
+            BASE64_STANDARD.decode(wireguard.as_bytes()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_argon2id() {
+        for (algorithm, len) in [
+            (Algorithm::Argon2id128, 16),
+            (Algorithm::Argon2id256, 32),
+            (Algorithm::Argon2id384, 48),
+        ] {
+            let key = algorithm.key(&argon2(), b"password", b"saltsalt").unwrap();
+            assert_eq!(BASE64_STANDARD.decode(key.as_bytes()).unwrap().len(), len);
+        }
+    }
+
+    #[test]
+    fn test_blake3() {
+        let key256 = Algorithm::Blake3_256
+            .key(&argon2(), b"password", b"saltsalt")
+            .unwrap();
+        assert_eq!(&*key256, "gfZQUnF7TIh9ZZc9fxaaX6+Zm6YUSAY4aDHaXvZepzE=");
+        let key512 = Algorithm::Blake3_512
+            .key(&argon2(), b"password", b"saltsalt")
+            .unwrap();
+        assert_eq!(
+            &*key512,
+            "5CAUelZP3zVradDyhwwPmQ3S5sknxL78mHuMmpVPUoqghen3KrlWoq/XLFpM2UXeF7n4mcb3izHnHoJrCEeLxQ==",
+        );
+        // the 512-bit key does not give away the 256-bit one
+        let key256 = BASE64_STANDARD.decode(key256.as_bytes()).unwrap();
+        let key512 = BASE64_STANDARD.decode(key512.as_bytes()).unwrap();
+        assert!(!key512.starts_with(&key256));
+    }
+
+    #[test]
+    fn test_ssh_ed25519() {
+        let key = Algorithm::SshEd25519
+            .key(&argon2(), b"password", b"saltsalt")
+            .unwrap();
+        assert!(key.starts_with("ssh-ed25519 "));
+        let public_key = ssh_key::PublicKey::from_openssh(&key).unwrap();
+        assert_eq!(public_key.algorithm(), ssh_key::Algorithm::Ed25519);
+        assert_eq!(public_key.comment(), "s2k-derived");
+        // the seed is not the plain argon2id key
+        let mut argon2id = [0u8; 32];
+        argon2()
+            .hash_password_into(b"password", b"saltsalt", &mut argon2id)
+            .unwrap();
+        let keypair = ssh_key::private::Ed25519Keypair::from_seed(&argon2id);
+        assert_ne!(public_key.key_data(), &keypair.public.into());
+    }
+
+    // RFC 4226 section 5.3 with dynamic truncation
+    fn hotp<D>(secret: &[u8], counter: u64, digits: u32) -> u32
+    where
+        D: Mac + KeyInit,
+    {
+        let mut mac = <D as Mac>::new_from_slice(secret).unwrap();
+        mac.update(&counter.to_be_bytes());
+        let hash = mac.finalize().into_bytes();
+        let offset = (hash[hash.len() - 1] & 0xf) as usize;
+        let code = u32::from_be_bytes(hash[offset..offset + 4].try_into().unwrap()) & 0x7fff_ffff;
+        code % 10u32.pow(digits)
+    }
+
+    #[test]
+    fn test_hotp() {
+        // RFC 6238 appendix B, T = 59
+        assert_eq!(
+            hotp::<Hmac<Sha256>>(b"12345678901234567890123456789012", 1, 8),
+            46119246,
+        );
+        assert_eq!(
+            hotp::<Hmac<Sha512>>(
+                b"1234567890123456789012345678901234567890123456789012345678901234",
+                1,
+                8,
+            ),
+            90693936,
+        );
+    }
+
+    #[test]
+    fn test_hmac_key() {
+        let argon2id = BASE64_STANDARD
+            .decode(
+                Algorithm::Argon2id256
+                    .key(&argon2(), b"password", b"saltsalt")
+                    .unwrap()
+                    .as_bytes(),
+            )
+            .unwrap();
+        for (algorithm, len, codes) in [
+            (Algorithm::HmacSha256Key, 32, [315648, 652739]),
+            (Algorithm::HmacSha512Key, 64, [867601, 493128]),
+        ] {
+            let key = algorithm.key(&argon2(), b"password", b"saltsalt").unwrap();
+            // unpadded RFC 4648 base32, as expected by authenticator apps
+            assert!(key.bytes().all(|b| matches!(b, b'A'..=b'Z' | b'2'..=b'7')));
+            let secret = data_encoding::BASE32_NOPAD.decode(key.as_bytes()).unwrap();
+            assert_eq!(secret.len(), len);
+            // the third party that stores the secret learns nothing about the other keys
+            assert_ne!(secret[..32], argon2id[..]);
+            // lets a change to the derived secret show up as a different code
+            let code = |counter| {
+                if algorithm == Algorithm::HmacSha256Key {
+                    hotp::<Hmac<Sha256>>(&secret, counter, 6)
+                } else {
+                    hotp::<Hmac<Sha512>>(&secret, counter, 6)
+                }
+            };
+            assert_eq!([code(0), code(1)], codes, "{algorithm:?}");
+        }
+    }
+
+    #[test]
+    fn test_encode_words() {
+        assert_eq!(EFF_LARGE_WORDLIST.len(), 7776);
+        assert_eq!(
+            super::encode_words(&hex!("1E60"), &EFF_LARGE_WORDLIST, 3),
+            ["abacus", "abdomen", "abacus"],
+        );
+        assert_eq!(
+            super::encode_words(&hex!("FFFFFFFF"), &["0", "1"], 4),
+            ["1", "1", "1", "1"],
+        );
+        // an empty key is the number zero
+        assert_eq!(
+            super::encode_words(b"", &["0", "1", "2"], 6),
+            ["0", "0", "0", "0", "0", "0"],
+        );
+        assert_eq!(super::encode_words(b"", &["0", "1"], 1), ["0"]);
+        assert!(super::encode_words(b"", &["a", "b"], 0).is_empty());
+        assert!(super::encode_words(&hex!("1E60"), &EFF_LARGE_WORDLIST, 0).is_empty());
+        assert!(super::encode_words(&hex!("1E60"), &[], 3).is_empty());
+    }
+
+    #[test]
+    fn test_fragment() {
+        let fragment = Fragment {
+            algorithm: Some(Algorithm::S2kSha256),
+            salt: Some("my service".to_owned()),
+        };
+        assert_eq!(fragment.to_string(), "#algorithm=S2kSha256&salt=my+service");
+        assert_eq!(Fragment::parse(&fragment.to_string()), fragment);
+        assert_eq!(
+            Fragment::parse("#algorithm=unknown&password=secret"),
+            Fragment::default(),
+        );
+    }
+
+    #[test]
+    fn test_copy_on_reveal() {
+        assert!(!copy_on_reveal(""));
+        assert!(copy_on_reveal("?copy_on_reveal"));
+        assert!(copy_on_reveal("?timeout=5&copy_on_reveal=true"));
+        assert!(!copy_on_reveal("?copy_on_reveal=false"));
+
+        assert_eq!(toggle_visible(false, true), (true, true));
+        assert_eq!(toggle_visible(true, true), (false, false));
+        assert_eq!(toggle_visible(false, false), (true, false));
+    }
+
+    #[test]
+    fn test_copy_timeout() {
+        assert_eq!(copy_timeout(""), Duration::from_secs(1));
+        assert_eq!(copy_timeout("?timeout=5"), Duration::from_secs(5));
+        assert_eq!(copy_timeout("?foo=bar&timeout=0"), Duration::ZERO);
+        assert_eq!(copy_timeout("?timeout=soon"), Duration::from_secs(1));
+    }
 
     #[test]
     fn test_s2k() {
@@ -0,0 +1,38 @@
+// RFC 9106 reference vectors; run with `cargo test -- --ignored`
+
+use argon2::{Algorithm, Argon2, AssociatedData, ParamsBuilder, Version};
+use hex_literal::hex;
+
+// the inputs shared by every vector in RFC 9106, section 5
+fn rfc9106_tag(algorithm: Algorithm) -> [u8; 32] {
+    let params = ParamsBuilder::new()
+        .m_cost(32)
+        .t_cost(3)
+        .p_cost(4)
+        .data(AssociatedData::new(&[0x04; 12]).unwrap())
+        .build()
+        .unwrap();
+    let argon2 = Argon2::new_with_secret(&[0x03; 8], algorithm, Version::V0x13, params).unwrap();
+    let mut tag = [0; 32];
+    argon2
+        .hash_password_into(&[0x01; 32], &[0x02; 16], &mut tag)
+        .unwrap();
+    tag
+}
+
+#[test]
+#[ignore]
+fn test_rfc9106() {
+    assert_eq!(
+        rfc9106_tag(Algorithm::Argon2d),
+        hex!("512b391b6f1162975371d30919734294f868e3be3984f3c1a13a4db9fabe4acb"),
+    );
+    assert_eq!(
+        rfc9106_tag(Algorithm::Argon2i),
+        hex!("c814d9d1dc7f37aa13f0d77f2494bda1c8de6b016dd388d29952a4c4672b6ce8"),
+    );
+    assert_eq!(
+        rfc9106_tag(Algorithm::Argon2id),
+        hex!("0d640df58d78766c08c037a34a8b53c9d01ef0452d75b65eb52520e96b01e659"),
+    );
+}